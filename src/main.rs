@@ -1,52 +1,257 @@
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
-    execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
-};
+mod practice;
+mod terminal;
+mod words;
+
+use crossterm::event::{self, Event, KeyCode};
 use ratatui::{
-    Terminal,
-    backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout},
     style::{Color, Style},
+    symbols,
     text::{Line, Span}, // ← FIXED
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Axis, Block, Borders, Chart, Dataset, List, ListItem, Paragraph},
 };
-use std::io;
+use practice::PracticeText;
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
+use terminal::TerminalGuard;
+use words::TestMode;
+
+/// Words kept in reserve past the typing position before a timed test tops
+/// the sample back up.
+const TIMED_TOP_UP_THRESHOLD: usize = 5;
+const TIMED_TOP_UP_COUNT: usize = 20;
+
+/// How often a new WPM sample is pushed onto the live graph.
+const WPM_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+/// Trailing window used to compute instantaneous WPM, so the graph reflects
+/// current speed rather than the test's speed-since-start average.
+const WPM_WINDOW_SECS: f64 = 5.0;
+
+/// Which screen is currently active. Mirrors the `InputMode` pattern used by
+/// ratatui's `user_input` example, but drives the whole app rather than a
+/// single widget.
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+enum Screen {
+    #[default]
+    Menu,
+    Typing,
+    Results,
+}
 
 //app state
 #[derive(Default)]
 struct App {
+    screen: Screen,
+    mode: TestMode,
     sample: String,
     typed: String,
     start_time: Option<Instant>,
     finished: bool, // ← fixed typo
     quit: bool,
+    /// (time, was this keystroke correct) for every character typed so far,
+    /// used to compute a trailing-window instantaneous WPM.
+    char_log: Vec<(Instant, bool)>,
+    /// (elapsed_seconds, instantaneous_wpm) samples for the live graph.
+    wpm_samples: Vec<(f64, f64)>,
+    last_sample_at: Option<Instant>,
+    /// (attempts, errors) per target character, recorded the moment each
+    /// character is typed so a mistake is remembered even after backspace.
+    mistakes: HashMap<char, (u32, u32)>,
+    /// Every character ever typed, correct or not (backspacing doesn't
+    /// undo these), used for gross WPM/CPM.
+    total_keystrokes: u32,
+    /// The subset of `total_keystrokes` that matched the target character.
+    correct_keystrokes: u32,
+    /// Custom text loaded via `--file`/stdin, if any. Takes priority over
+    /// `mode`'s generated samples when present.
+    practice: Option<PracticeText>,
+    practice_page: usize,
 }
 
 impl App {
-    fn new() -> Self {
+    fn new(practice: Option<PracticeText>) -> Self {
+        let mode = TestMode::default();
+        let sample = match &practice {
+            Some(p) => p.page(0).to_string(),
+            None => words::initial_sample(mode),
+        };
         Self {
-            sample: "The quick brown fox jumps over the lazy dog.".to_string(),
+            mode,
+            sample,
+            practice,
             ..Default::default()
         }
     }
+
+    /// Draws a fresh sample for the selected mode (or the current page of
+    /// custom text) and drops into the Typing screen. Used when starting a
+    /// test from the Menu.
+    fn start_test(&mut self) {
+        self.sample = match &self.practice {
+            Some(p) => p.page(self.practice_page).to_string(),
+            None => words::initial_sample(self.mode),
+        };
+        self.reset_progress();
+    }
+
+    /// Retries the sample that was just typed, unchanged, so `r` on the
+    /// Results screen is an actual retry rather than a new random draw.
+    fn retry_test(&mut self) {
+        self.reset_progress();
+    }
+
+    /// Clears typing progress (buffer, timing, mistakes, graph samples)
+    /// without touching `self.sample`.
+    fn reset_progress(&mut self) {
+        self.typed.clear();
+        self.start_time = None;
+        self.finished = false;
+        self.char_log.clear();
+        self.wpm_samples.clear();
+        self.last_sample_at = None;
+        self.mistakes.clear();
+        self.total_keystrokes = 0;
+        self.correct_keystrokes = 0;
+        self.screen = Screen::Typing;
+    }
+
+    /// Sends the user back to the menu without discarding the sample.
+    fn return_to_menu(&mut self) {
+        self.screen = Screen::Menu;
+    }
+
+    /// Cycles to the next test mode, wrapping around.
+    fn next_mode(&mut self) {
+        let idx = TestMode::ALL.iter().position(|m| *m == self.mode).unwrap_or(0);
+        self.mode = TestMode::ALL[(idx + 1) % TestMode::ALL.len()];
+    }
+
+    /// Cycles to the previous test mode, wrapping around.
+    fn previous_mode(&mut self) {
+        let idx = TestMode::ALL.iter().position(|m| *m == self.mode).unwrap_or(0);
+        let len = TestMode::ALL.len();
+        self.mode = TestMode::ALL[(idx + len - 1) % len];
+    }
+
+    /// Called once per main-loop tick while Typing: ends timed tests whose
+    /// duration has elapsed, tops up the sample so it never runs out, and
+    /// samples the live WPM graph roughly once per second.
+    fn tick(&mut self) {
+        if self.screen != Screen::Typing || self.finished {
+            return;
+        }
+        let Some(start) = self.start_time else {
+            return;
+        };
+        if words::timed_mode_expired(self.mode, start) {
+            self.finish();
+            return;
+        }
+        if self.practice.is_none()
+            && matches!(self.mode, TestMode::Timed(_))
+            && words::words_remaining_after(&self.sample, self.typed.len()) < TIMED_TOP_UP_THRESHOLD
+        {
+            words::extend_with_words(&mut self.sample, TIMED_TOP_UP_COUNT);
+        }
+
+        let due = self
+            .last_sample_at
+            .map(|t| t.elapsed() >= WPM_SAMPLE_INTERVAL)
+            .unwrap_or(true);
+        if due {
+            let elapsed = start.elapsed().as_secs_f64();
+            self.wpm_samples.push((elapsed, self.instantaneous_wpm()));
+            self.last_sample_at = Some(Instant::now());
+        }
+    }
+
+    /// WPM computed from correctly typed characters within the last
+    /// `WPM_WINDOW_SECS`, so the graph tracks current speed rather than the
+    /// average since the test began.
+    fn instantaneous_wpm(&self) -> f64 {
+        let Some(start) = self.start_time else {
+            return 0.0;
+        };
+        let window = start.elapsed().as_secs_f64().min(WPM_WINDOW_SECS);
+        if window <= 0.0 {
+            return 0.0;
+        }
+        let cutoff = Duration::from_secs_f64(window);
+        let now = Instant::now();
+        let correct_chars = self
+            .char_log
+            .iter()
+            .filter(|(t, correct)| *correct && now.duration_since(*t) <= cutoff)
+            .count();
+        (correct_chars as f64 / 5.0) / (window / 60.0)
+    }
+
+    /// Marks the test done. For multi-page custom text this advances
+    /// `practice_page` so the *next* `start_test()` (a fresh test from the
+    /// Menu) picks up the next page. `retry_test()` never re-reads
+    /// `practice_page` — it reuses `self.sample` as-is — so `r` still
+    /// retries the page that was just typed, not the one this bumps to.
+    fn finish(&mut self) {
+        self.finished = true;
+        self.screen = Screen::Results;
+        if let Some(practice) = &self.practice {
+            if practice.page_count() > 1 {
+                self.practice_page = (self.practice_page + 1) % practice.page_count();
+            }
+        }
+    }
+
+    fn minutes(&self) -> f64 {
+        self.start_time
+            .map(|t| t.elapsed().as_secs_f64() / 60.0)
+            .unwrap_or(0.0)
+    }
+
+    /// Gross WPM: every keystroke counts, correct or not.
+    fn gross_wpm(&self) -> f64 {
+        let minutes = self.minutes();
+        if minutes <= 0.0 {
+            return 0.0;
+        }
+        (self.total_keystrokes as f64 / 5.0) / minutes
+    }
+
+    /// Net WPM: correct keystrokes, less the mistakes still present in the
+    /// final buffer, both in 5-character words per minute; never below zero.
+    fn net_wpm(&self) -> f64 {
+        let minutes = self.minutes();
+        if minutes <= 0.0 {
+            return 0.0;
+        }
+        let uncorrected_errors = self
+            .typed
+            .chars()
+            .zip(self.sample.chars())
+            .filter(|(t, s)| t != s)
+            .count() as f64;
+        (((self.correct_keystrokes as f64 / 5.0) - (uncorrected_errors / 5.0)) / minutes).max(0.0)
+    }
+
+    /// Raw characters-per-minute, ignoring correctness entirely.
+    fn raw_cpm(&self) -> f64 {
+        let minutes = self.minutes();
+        if minutes <= 0.0 {
+            return 0.0;
+        }
+        self.total_keystrokes as f64 / minutes
+    }
 }
 
 // ===== Main =====
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // --- Setup ---
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let practice = practice::from_args(std::env::args().skip(1))?;
 
-    let mut app = App::new(); // ← create app BEFORE loop
+    let mut guard = TerminalGuard::new()?;
+    let mut app = App::new(practice); // ← create app BEFORE loop
 
     // --- Main Loop ---
     loop {
-        terminal.draw(|f| ui(f, &app))?;
+        guard.terminal.draw(|f| ui(f, &app))?;
 
         if app.quit {
             break;
@@ -57,16 +262,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 handle_key(key, &mut app);
             }
         }
-    }
 
-    // --- Cleanup ---
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+        app.tick();
+    }
 
     Ok(())
 }
@@ -74,10 +272,56 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 // ui rendering
 
 fn ui(f: &mut ratatui::Frame, app: &App) {
+    match app.screen {
+        Screen::Menu => ui_menu(f, app),
+        Screen::Typing => ui_typing(f, app),
+        Screen::Results => ui_results(f, app),
+    }
+}
+
+fn ui_menu(f: &mut ratatui::Frame, app: &App) {
+    if let Some(practice) = &app.practice {
+        let label = format!(
+            "Custom text loaded ({} page{})",
+            practice.page_count(),
+            if practice.page_count() == 1 { "" } else { "s" }
+        );
+        let list = List::new(vec![ListItem::new(label)]).block(
+            Block::default()
+                .title("tser — Enter to begin, Esc to quit")
+                .borders(Borders::ALL),
+        );
+        f.render_widget(list, f.area());
+        return;
+    }
+
+    let items: Vec<ListItem> = TestMode::ALL
+        .iter()
+        .map(|m| {
+            let label = format!("{}{}", if *m == app.mode { "> " } else { "  " }, m.label());
+            ListItem::new(label)
+        })
+        .collect();
+    let list = List::new(items).block(
+        Block::default()
+            .title("tser — ←/→ choose a test, Enter to begin, Esc to quit")
+            .borders(Borders::ALL),
+    );
+    f.render_widget(list, f.area());
+}
+
+fn ui_typing(f: &mut ratatui::Frame, app: &App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(2)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .constraints(
+            [
+                Constraint::Percentage(40),
+                Constraint::Percentage(20),
+                Constraint::Percentage(40),
+            ]
+            .as_ref(),
+        )
         .split(f.area());
 
     // Top: colored text to type
@@ -97,13 +341,7 @@ fn ui(f: &mut ratatui::Frame, app: &App) {
     f.render_widget(paragraph, chunks[0]);
 
     // Bottom: stats
-    let stats_text = if app.finished {
-        let minutes = app.start_time.unwrap().elapsed().as_secs_f64() / 60.0;
-        let words = app.sample.chars().filter(|c| *c == ' ').count() + 1; // rough word count
-        let wpm = (words as f64) / minutes;
-        let accuracy = accuracy(app);
-        format!("Finished! WPM: {:.0} | Accuracy: {:.1}%", wpm, accuracy)
-    } else if app.start_time.is_some() {
+    let stats_text = if app.start_time.is_some() {
         let secs = app.start_time.unwrap().elapsed().as_secs_f64();
         format!("Typing... {:.1} seconds", secs)
     } else {
@@ -113,44 +351,163 @@ fn ui(f: &mut ratatui::Frame, app: &App) {
     let stats =
         Paragraph::new(stats_text).block(Block::default().title("Stats").borders(Borders::ALL));
     f.render_widget(stats, chunks[1]);
+
+    // Bottom: live WPM graph
+    let max_x = app.wpm_samples.last().map(|(x, _)| *x).unwrap_or(1.0).max(1.0);
+    let max_y = app
+        .wpm_samples
+        .iter()
+        .map(|(_, y)| *y)
+        .fold(0.0_f64, f64::max)
+        .max(10.0);
+
+    let dataset = Dataset::default()
+        .name("WPM")
+        .marker(symbols::Marker::Braille)
+        .style(Style::default().fg(Color::Cyan))
+        .data(&app.wpm_samples);
+
+    let chart = Chart::new(vec![dataset])
+        .block(Block::default().title("WPM").borders(Borders::ALL))
+        .x_axis(
+            Axis::default()
+                .bounds([0.0, max_x])
+                .labels(vec!["0".into(), format!("{:.0}s", max_x).into()]),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max_y])
+                .labels(vec!["0".into(), format!("{:.0}", max_y).into()]),
+        );
+    f.render_widget(chart, chunks[2]);
+}
+
+fn ui_results(f: &mut ratatui::Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+        .split(f.area());
+
+    let gross_wpm = app.gross_wpm();
+    let net_wpm = app.net_wpm();
+    let cpm = app.raw_cpm();
+    let accuracy = accuracy(app);
+
+    let text = format!(
+        "Finished! Net WPM: {:.0} | Gross WPM: {:.0} | CPM: {:.0} | Accuracy: {:.1}%\n\nr: retry   m: menu",
+        net_wpm, gross_wpm, cpm, accuracy
+    );
+    let paragraph =
+        Paragraph::new(text).block(Block::default().title("Results").borders(Borders::ALL));
+    f.render_widget(paragraph, chunks[0]);
+
+    // Worst characters by error rate, so the typist can see what's holding
+    // them back even though backspace already erased it from `typed`.
+    let mut ranked: Vec<(char, u32, u32)> = app
+        .mistakes
+        .iter()
+        .filter(|(_, (attempts, _))| *attempts > 0)
+        .map(|(c, (attempts, errors))| (*c, *attempts, *errors))
+        .collect();
+    ranked.sort_by(|a, b| {
+        let rate_a = a.2 as f64 / a.1 as f64;
+        let rate_b = b.2 as f64 / b.1 as f64;
+        rate_b.partial_cmp(&rate_a).unwrap()
+    });
+
+    let items: Vec<ListItem> = ranked
+        .into_iter()
+        .filter(|(_, _, errors)| *errors > 0)
+        .take(5)
+        .map(|(c, attempts, errors)| {
+            let pct = (errors as f64 / attempts as f64) * 100.0;
+            ListItem::new(format!("{c:?}: {errors} errors / {attempts} ({pct:.0}%)"))
+        })
+        .collect();
+    let heatmap = List::new(items).block(
+        Block::default()
+            .title("Worst characters")
+            .borders(Borders::ALL),
+    );
+    f.render_widget(heatmap, chunks[1]);
 }
 
 // ===== Input Handling =====
 fn handle_key(key: crossterm::event::KeyEvent, app: &mut App) {
+    match app.screen {
+        Screen::Menu => handle_key_menu(key, app),
+        Screen::Typing => handle_key_typing(key, app),
+        Screen::Results => handle_key_results(key, app),
+    }
+}
+
+fn handle_key_menu(key: crossterm::event::KeyEvent, app: &mut App) {
+    match key.code {
+        KeyCode::Esc => app.quit = true,
+        KeyCode::Enter => app.start_test(),
+        KeyCode::Left => app.previous_mode(),
+        KeyCode::Right => app.next_mode(),
+        _ => {}
+    }
+}
+
+fn handle_key_typing(key: crossterm::event::KeyEvent, app: &mut App) {
     match key.code {
         KeyCode::Esc => app.quit = true,
         KeyCode::Backspace => {
             app.typed.pop();
         }
         KeyCode::Char(c) => {
-            if !app.finished {
-                if app.start_time.is_none() {
-                    app.start_time = Some(Instant::now());
+            if app.start_time.is_none() {
+                app.start_time = Some(Instant::now());
+            }
+            app.total_keystrokes += 1;
+            if let Some(target) = app.sample.chars().nth(app.typed.chars().count()) {
+                let correct = target == c;
+                app.char_log.push((Instant::now(), correct));
+                let entry = app.mistakes.entry(target).or_insert((0, 0));
+                entry.0 += 1;
+                if correct {
+                    app.correct_keystrokes += 1;
+                } else {
+                    entry.1 += 1;
                 }
-                app.typed.push(c);
-                try_finish(app);
             }
+            app.typed.push(c);
+            try_finish(app);
         }
         _ => {}
     }
 }
 
+fn handle_key_results(key: crossterm::event::KeyEvent, app: &mut App) {
+    match key.code {
+        KeyCode::Esc => app.quit = true,
+        KeyCode::Char('r') => app.retry_test(),
+        KeyCode::Char('m') => app.return_to_menu(),
+        _ => {}
+    }
+}
+
 fn try_finish(app: &mut App) {
-    if app.typed.len() >= app.sample.len() && app.typed == app.sample {
-        app.finished = true;
+    let finishes_on_completion = app.practice.is_some() || matches!(app.mode, TestMode::Words(_));
+    if finishes_on_completion && app.typed.len() >= app.sample.len() && app.typed == app.sample {
+        app.finish();
     }
 }
 
+/// Accuracy over every keystroke ever pressed, not just what survives in
+/// the final buffer — a mistake that was backspaced away still counts.
 fn accuracy(app: &App) -> f64 {
-    if app.typed.is_empty() {
+    let (attempts, errors) = app
+        .mistakes
+        .values()
+        .fold((0u32, 0u32), |(attempts, errors), (a, e)| {
+            (attempts + a, errors + e)
+        });
+    if attempts == 0 {
         return 100.0;
     }
-    let correct: usize = app
-        .typed
-        .chars()
-        .zip(app.sample.chars())
-        .filter(|(t, s)| t == s)
-        .count();
-    (correct as f64 / app.typed.len() as f64) * 100.0
+    ((attempts - errors) as f64 / attempts as f64) * 100.0
 }
-
@@ -0,0 +1,62 @@
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+
+/// Words per page when a long custom text is split into chunks, so each
+/// typing test stays a reasonable length instead of one giant sample.
+const PAGE_WORD_COUNT: usize = 60;
+
+/// Custom practice text loaded from a file or stdin, normalized into a
+/// single whitespace-separated stream and paginated.
+pub struct PracticeText {
+    pages: Vec<String>,
+}
+
+impl PracticeText {
+    fn from_raw(raw: &str) -> Self {
+        let words: Vec<&str> = raw.split_whitespace().collect();
+        let pages = if words.is_empty() {
+            vec![String::new()]
+        } else {
+            words
+                .chunks(PAGE_WORD_COUNT)
+                .map(|chunk| chunk.join(" "))
+                .collect()
+        };
+        Self { pages }
+    }
+
+    pub fn page(&self, index: usize) -> &str {
+        &self.pages[index % self.pages.len()]
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+}
+
+/// Looks for `--file <path>` among the given arguments, falling back to
+/// piped stdin, and returns `None` when neither is present so callers fall
+/// back to the built-in sample.
+pub fn from_args(mut args: impl Iterator<Item = String>) -> io::Result<Option<PracticeText>> {
+    let mut file_path = None;
+    while let Some(arg) = args.next() {
+        if arg == "--file" {
+            file_path = args.next();
+        }
+    }
+
+    if let Some(path) = file_path {
+        let contents = fs::read_to_string(path)?;
+        return Ok(Some(PracticeText::from_raw(&contents)));
+    }
+
+    if !io::stdin().is_terminal() {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf)?;
+        if !buf.trim().is_empty() {
+            return Ok(Some(PracticeText::from_raw(&buf)));
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,91 @@
+use rand::seq::SliceRandom;
+use std::time::{Duration, Instant};
+
+/// Pool of common English words, shipped with the binary so word generation
+/// needs no network access or data files at runtime.
+const WORD_LIST: &str = include_str!("words.txt");
+
+fn words() -> Vec<&'static str> {
+    WORD_LIST.lines().filter(|w| !w.is_empty()).collect()
+}
+
+/// How a test decides when it's done.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TestMode {
+    /// Finish after typing exactly this many words.
+    Words(usize),
+    /// Finish once this much time has elapsed, regardless of progress.
+    Timed(Duration),
+}
+
+impl Default for TestMode {
+    fn default() -> Self {
+        TestMode::Words(25)
+    }
+}
+
+impl TestMode {
+    /// The options offered on the Menu screen, in display order.
+    pub const ALL: [TestMode; 6] = [
+        TestMode::Words(25),
+        TestMode::Words(50),
+        TestMode::Words(100),
+        TestMode::Timed(Duration::from_secs(15)),
+        TestMode::Timed(Duration::from_secs(30)),
+        TestMode::Timed(Duration::from_secs(60)),
+    ];
+
+    pub fn label(&self) -> String {
+        match self {
+            TestMode::Words(n) => format!("{n} words"),
+            TestMode::Timed(d) => format!("{}s", d.as_secs()),
+        }
+    }
+}
+
+/// Builds a sample sentence of `count` random words.
+pub fn sample_of_length(count: usize) -> String {
+    let pool = words();
+    let mut rng = rand::thread_rng();
+    (0..count)
+        .map(|_| *pool.choose(&mut rng).unwrap_or(&"word"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Appends `count` more random words to `sample`, used to keep a timed test
+/// supplied with text so the typist never runs out before time expires.
+pub fn extend_with_words(sample: &mut String, count: usize) {
+    let pool = words();
+    let mut rng = rand::thread_rng();
+    for _ in 0..count {
+        sample.push(' ');
+        sample.push_str(pool.choose(&mut rng).unwrap_or(&"word"));
+    }
+}
+
+/// A fresh sample for the given mode: a fixed word count, or an initial
+/// batch for timed mode that `extend_with_words` tops up as it runs low.
+pub fn initial_sample(mode: TestMode) -> String {
+    match mode {
+        TestMode::Words(n) => sample_of_length(n),
+        TestMode::Timed(_) => sample_of_length(40),
+    }
+}
+
+/// How many words remain unreached past the current typing position.
+pub fn words_remaining_after(sample: &str, typed_len: usize) -> usize {
+    sample
+        .get(typed_len..)
+        .unwrap_or("")
+        .split_whitespace()
+        .count()
+}
+
+/// Has `Timed(duration)` mode run out since `start`?
+pub fn timed_mode_expired(mode: TestMode, start: Instant) -> bool {
+    match mode {
+        TestMode::Timed(duration) => start.elapsed() >= duration,
+        TestMode::Words(_) => false,
+    }
+}